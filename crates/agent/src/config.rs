@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::generation::GenerationOptions;
+
+/// A set of named provider setups, e.g. `agents.toml`, selectable with `--profile`
+/// instead of having to pass provider flags on every invocation.
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub profiles: HashMap<String, Profile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Profile {
+    Ollama {
+        url: Url,
+        model: String,
+        #[serde(default)]
+        generation: GenerationOptions,
+        /// Pull the model from the Ollama library if it isn't available locally, mirroring
+        /// the `ollama` subcommand's `--pull` flag.
+        #[serde(default)]
+        pull: bool,
+    },
+    OpenAiCompatible {
+        url: Url,
+        #[serde(rename = "api_key_env")]
+        api_key_env_var: String,
+        model: String,
+        #[serde(default)]
+        generation: GenerationOptions,
+    },
+}
+
+impl Config {
+    pub async fn load(path: &Path) -> Result<Self> {
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        toml::from_str(&contents)
+            .with_context(|| format!("failed to parse config file {}", path.display()))
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        self.profiles
+            .get(name)
+            .with_context(|| format!("no profile named \"{name}\" in config file"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles() -> Result<()> {
+        let toml = r#"
+[profiles.local]
+kind = "ollama"
+url = "http://localhost:11434"
+model = "llama3"
+pull = true
+
+[profiles.remote]
+kind = "open-ai-compatible"
+url = "https://openrouter.ai/api/v1"
+api_key_env = "OPENROUTER_API_KEY"
+model = "gpt-4o"
+generation.num_ctx = 8192
+"#;
+        let config: Config = toml::from_str(toml)?;
+
+        match config.profile("local")? {
+            Profile::Ollama {
+                url,
+                model,
+                generation,
+                pull,
+            } => {
+                assert_eq!(url.as_str(), "http://localhost:11434/");
+                assert_eq!(model, "llama3");
+                assert_eq!(generation.num_ctx, None);
+                assert!(*pull);
+            }
+            Profile::OpenAiCompatible { .. } => panic!("expected an Ollama profile"),
+        }
+
+        match config.profile("remote")? {
+            Profile::OpenAiCompatible {
+                url,
+                api_key_env_var,
+                model,
+                generation,
+            } => {
+                assert_eq!(url.as_str(), "https://openrouter.ai/api/v1");
+                assert_eq!(api_key_env_var, "OPENROUTER_API_KEY");
+                assert_eq!(model, "gpt-4o");
+                assert_eq!(generation.num_ctx, Some(8192));
+            }
+            Profile::Ollama { .. } => panic!("expected an OpenAiCompatible profile"),
+        }
+
+        assert!(config.profile("missing").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use clap::Args;
+use serde::Deserialize;
+
+/// Generation parameters threaded through to whichever provider is in use, so long
+/// agentic runs can be tuned instead of relying on each provider's defaults.
+///
+/// Can be populated from CLI flags or from a profile in the config file; the latter
+/// uses the same field names so `agents.toml` and `--help` stay in sync.
+#[derive(Debug, Args, Clone, Deserialize)]
+#[serde(default)]
+pub struct GenerationOptions {
+    #[arg(long, global = true, help = "Sampling temperature")]
+    pub temperature: Option<f32>,
+    #[arg(long, global = true, help = "Nucleus sampling probability mass")]
+    pub top_p: Option<f32>,
+    #[arg(
+        long,
+        global = true,
+        help = "Context window size in tokens. Ollama silently truncates context beyond this \
+                and exposes no API to query a model's real limit, so this should be set to match \
+                whatever model is loaded. Defaults to 4096 if unset. Only used by Ollama; \
+                OpenAI-compatible providers ignore it"
+    )]
+    pub num_ctx: Option<u64>,
+    #[arg(long, global = true, help = "Maximum number of tokens to generate")]
+    pub max_tokens: Option<i32>,
+    #[arg(
+        long,
+        global = true,
+        value_delimiter = ',',
+        help = "Stop sequence(s) that end generation, comma-separated"
+    )]
+    pub stop: Vec<String>,
+    #[arg(long, global = true, help = "Random seed for reproducible generations")]
+    pub seed: Option<i32>,
+}
+
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        Self {
+            temperature: None,
+            top_p: None,
+            num_ctx: None,
+            max_tokens: None,
+            stop: Vec::new(),
+            seed: None,
+        }
+    }
+}
+
+/// The context window Ollama is given when `num_ctx` isn't set by either a profile or a
+/// CLI flag.
+pub const DEFAULT_NUM_CTX: u64 = 4096;
+
+impl GenerationOptions {
+    /// Layers `overrides` (e.g. CLI flags) on top of `self` (e.g. a profile's settings),
+    /// taking each field from `overrides` only where the user actually supplied it, so
+    /// combining `--profile` with a generation flag overrides just that one setting
+    /// instead of discarding the rest of the profile's generation config.
+    pub fn merge(self, overrides: GenerationOptions) -> GenerationOptions {
+        GenerationOptions {
+            temperature: overrides.temperature.or(self.temperature),
+            top_p: overrides.top_p.or(self.top_p),
+            num_ctx: overrides.num_ctx.or(self.num_ctx),
+            max_tokens: overrides.max_tokens.or(self.max_tokens),
+            stop: if overrides.stop.is_empty() { self.stop } else { overrides.stop },
+            seed: overrides.seed.or(self.seed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_num_ctx_defaults_when_absent() -> Result<(), toml::de::Error> {
+        let options: GenerationOptions = toml::from_str("")?;
+        assert_eq!(options.num_ctx, None);
+        assert_eq!(options.temperature, None);
+        assert!(options.stop.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_num_ctx_overridden() -> Result<(), toml::de::Error> {
+        let options: GenerationOptions = toml::from_str("num_ctx = 8192\ntemperature = 0.5")?;
+        assert_eq!(options.num_ctx, Some(8192));
+        assert_eq!(options.temperature, Some(0.5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_only_overrides_fields_the_cli_set() {
+        let profile = GenerationOptions {
+            temperature: Some(0.2),
+            num_ctx: Some(8192),
+            stop: vec!["STOP".to_string()],
+            ..Default::default()
+        };
+        let cli = GenerationOptions {
+            temperature: Some(0.9),
+            ..Default::default()
+        };
+
+        let merged = profile.merge(cli);
+        assert_eq!(merged.temperature, Some(0.9));
+        assert_eq!(merged.num_ctx, Some(8192));
+        assert_eq!(merged.stop, vec!["STOP".to_string()]);
+    }
+}
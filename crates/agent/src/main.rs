@@ -3,14 +3,19 @@ use std::path::PathBuf;
 
 use anyhow::{anyhow, bail, Context, Result};
 use clap::{command, Parser, Subcommand};
+use futures::StreamExt;
 use handlebars::Handlebars;
 use ollama_rs::Ollama;
 use openai_api_rs::v1::api::OpenAIClient;
+use regex::Regex;
 use reqwest::Url;
+use simple_agent::config::{Config, Profile};
+use simple_agent::generation::GenerationOptions;
 use simple_agent::ollama::OllamaChatProvider;
-use simple_agent::openrouter::OpenRouterChatProvider;
+use simple_agent::openai_compatible::OpenAiCompatibleChatProvider;
+use simple_agent::policy::ExecutionPolicy;
 use simple_agent::providers::{ChatProvider, ChatProviders};
-use simple_agent::run::run_agent;
+use simple_agent::run::{run_agent, run_agent_tools};
 use tokio::{fs, signal};
 use tracing_subscriber::EnvFilter;
 
@@ -18,7 +23,20 @@ use tracing_subscriber::EnvFilter;
 #[command(version)]
 struct Cli {
     #[clap(subcommand)]
-    command: Command,
+    command: Option<Command>,
+    #[arg(
+        long,
+        global = true,
+        help = "Name of a profile to load from the config file, as an alternative to a subcommand"
+    )]
+    profile: Option<String>,
+    #[arg(
+        long,
+        global = true,
+        default_value = "agents.toml",
+        help = "Path to the profiles config file, used with --profile"
+    )]
+    config: PathBuf,
     #[arg(
         long,
         global = true,
@@ -40,6 +58,40 @@ struct Cli {
         help = "The path to the Handlebars template that will wrap the task"
     )]
     task_template: PathBuf,
+    #[command(flatten)]
+    generation: GenerationOptions,
+    #[arg(
+        long,
+        global = true,
+        help = "Prompt for y/n/edit on stdin before running each proposed command"
+    )]
+    approve: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Print proposed commands but don't run them, feeding back a synthetic \"not executed\" result"
+    )]
+    dry_run: bool,
+    #[arg(
+        long = "deny",
+        global = true,
+        help = "Regex pattern to auto-reject matching commands; can be passed multiple times"
+    )]
+    deny: Vec<String>,
+    #[arg(
+        long,
+        global = true,
+        help = "Use the provider's native tool-calling support instead of the YAML-fenced \
+                thoughts/run protocol"
+    )]
+    tools: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Wait for the full response before printing it, instead of streaming output \
+                incrementally as it's generated"
+    )]
+    no_stream: bool,
 }
 
 #[derive(Debug, Subcommand, Clone)]
@@ -49,6 +101,11 @@ enum Command {
         model: String,
         #[arg(long, short)]
         url: Url,
+        #[arg(
+            long,
+            help = "Pull the model from the Ollama library if it isn't available locally"
+        )]
+        pull: bool,
     },
     Openrouter {
         #[arg(long, help = "The model to use")]
@@ -122,32 +179,53 @@ async fn setup(cli: Cli) -> Result<()> {
     let task_values = HashMap::from([("task", task)]);
     let task_rendered = template_registry.render_template(&task_template, &task_values)?;
 
-    let chat_provider = match cli.command {
-        Command::Ollama { model, url } => {
-            let model = model.clone();
-            let ollama = Ollama::from_url(url);
-            let models = ollama.list_local_models().await.with_context(|| {
-                "couldn't list available models, is Ollama running and reachable?"
-            })?;
-            if !models.into_iter().any(|m| m.name == model) {
-                bail!("model {} not found", model);
-            }
-            let ollama = OllamaChatProvider::new(ollama, model.clone(), system);
+    let deny = cli
+        .deny
+        .iter()
+        .map(|pattern| Regex::new(pattern).with_context(|| format!("invalid deny pattern: {pattern}")))
+        .collect::<Result<Vec<_>>>()?;
+    let policy = ExecutionPolicy::new(cli.approve, cli.dry_run, deny);
 
-            println!("Model: {}", model);
-            println!("Chat ID: {}", ollama.chat_id);
-            ChatProviders::Ollama(ollama)
+    let chat_provider = match (cli.command, cli.profile) {
+        (Some(_), Some(_)) => bail!("pass either a subcommand or --profile, not both"),
+        (None, None) => bail!("pass either a subcommand or --profile"),
+        (Some(Command::Ollama { model, url, pull }), None) => {
+            build_ollama(url, model, pull, system, cli.generation).await?
+        }
+        (Some(Command::Openrouter { model, url }), None) => {
+            build_openai_compatible(url, "OPENROUTER_API_KEY", model, system, cli.generation)?
         }
-        Command::Openrouter { model, url } => {
-            let api_key = std::env::var("OPENROUTER_API_KEY")
-                .with_context(|| "OPENROUTER_API_KEY not found in environment")?;
-            let openrouter = OpenAIClient::builder()
-                .with_api_key(api_key)
-                .with_endpoint(url)
-                .build()
-                .map_err(|_e| anyhow!("couldn't build OpenRouter client"))?;
-            let openrouter = OpenRouterChatProvider::new(openrouter, model, system);
-            ChatProviders::OpenRouter(openrouter)
+        (None, Some(profile_name)) => {
+            let config = Config::load(&cli.config).await?;
+            match config.profile(&profile_name)? {
+                Profile::Ollama {
+                    url,
+                    model,
+                    generation,
+                    pull,
+                } => {
+                    build_ollama(
+                        url.clone(),
+                        model.clone(),
+                        *pull,
+                        system,
+                        generation.clone().merge(cli.generation),
+                    )
+                    .await?
+                }
+                Profile::OpenAiCompatible {
+                    url,
+                    api_key_env_var,
+                    model,
+                    generation,
+                } => build_openai_compatible(
+                    url.clone(),
+                    api_key_env_var,
+                    model.clone(),
+                    system,
+                    generation.clone().merge(cli.generation),
+                )?,
+            }
         }
     };
 
@@ -156,5 +234,78 @@ async fn setup(cli: Cli) -> Result<()> {
     println!("## First request");
     println!("{}", &first_message);
     println!();
-    run_agent(chat_provider, first_message).await
+    if cli.tools {
+        run_agent_tools(chat_provider, first_message, policy).await
+    } else {
+        run_agent(chat_provider, first_message, policy, !cli.no_stream).await
+    }
+}
+
+async fn build_ollama(
+    url: Url,
+    model: String,
+    pull: bool,
+    system: String,
+    generation: GenerationOptions,
+) -> Result<ChatProviders> {
+    let ollama = Ollama::from_url(url);
+    let models = ollama
+        .list_local_models()
+        .await
+        .with_context(|| "couldn't list available models, is Ollama running and reachable?")?;
+    if !models.into_iter().any(|m| m.name == model) {
+        if !pull {
+            bail!("model {} not found, pass --pull to download it", model);
+        }
+        pull_model(&ollama, &model).await?;
+    }
+    let ollama = OllamaChatProvider::new(ollama, model.clone(), system, generation);
+
+    println!("Model: {}", model);
+    println!("Chat ID: {}", ollama.chat_id);
+    Ok(ChatProviders::Ollama(ollama))
+}
+
+fn build_openai_compatible(
+    url: Url,
+    api_key_env_var: &str,
+    model: String,
+    system: String,
+    generation: GenerationOptions,
+) -> Result<ChatProviders> {
+    let api_key = std::env::var(api_key_env_var)
+        .with_context(|| format!("{api_key_env_var} not found in environment"))?;
+    let client = OpenAIClient::builder()
+        .with_api_key(api_key)
+        .with_endpoint(url)
+        .build()
+        .map_err(|_e| anyhow!("couldn't build OpenAI-compatible client"))?;
+    Ok(ChatProviders::OpenAiCompatible(OpenAiCompatibleChatProvider::new(
+        client, model, system, generation,
+    )))
+}
+
+/// Pulls `model` from the Ollama library, mirroring `ollama pull`'s progress output
+/// (each status line along with a completed/total byte count, where Ollama reports one).
+async fn pull_model(ollama: &Ollama, model: &str) -> Result<()> {
+    println!("Model {} not found locally, pulling...", model);
+    let mut stream = ollama
+        .pull_model_stream(model.to_string(), false)
+        .await
+        .with_context(|| format!("couldn't start pulling model {model}"))?;
+    while let Some(status) = stream.next().await {
+        let status = status.with_context(|| format!("error while pulling model {model}"))?;
+        match (status.completed, status.total) {
+            (Some(completed), Some(total)) if total > 0 => {
+                println!(
+                    "{}: {completed}/{total} ({:.1}%)",
+                    status.status,
+                    (completed as f64 / total as f64) * 100.0
+                );
+            }
+            _ => println!("{}", status.status),
+        }
+    }
+    println!("Pulled {}", model);
+    Ok(())
 }
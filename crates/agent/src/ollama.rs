@@ -1,36 +1,130 @@
 use anyhow::{Result, anyhow};
+use futures::{Stream, StreamExt};
 use ollama_rs::Ollama;
 use ollama_rs::generation::chat::request::ChatMessageRequest;
 use ollama_rs::generation::chat::{ChatMessage, MessageRole};
+use ollama_rs::generation::options::GenerationOptions as OllamaGenerationOptions;
+use ollama_rs::generation::tools::ToolInfo;
 
-use crate::providers::ChatProvider;
+use crate::generation::{DEFAULT_NUM_CTX, GenerationOptions};
+use crate::providers::{ChatProvider, ToolCallRequest, ToolTurn};
+use crate::tools::ToolDefinition;
 
 pub struct OllamaChatProvider {
     pub client: Ollama,
     pub model: String,
     pub system_prompt: String,
     pub chat_id: String,
+    pub generation: GenerationOptions,
+    /// The tools on offer for the current `--tools` run, resent on every request since
+    /// tool schemas are a per-request parameter, not part of the history Ollama keeps
+    /// server-side via `chat_id`.
+    tools: Vec<ToolInfo>,
+    /// Whether the system prompt has already been sent as part of the history Ollama
+    /// keeps server-side via `chat_id`, so it's only sent once rather than on every turn.
+    system_sent: bool,
 }
 
 impl OllamaChatProvider {
-    pub fn new(client: Ollama, model: String, system_prompt: String) -> Self {
+    pub fn new(
+        client: Ollama,
+        model: String,
+        system_prompt: String,
+        generation: GenerationOptions,
+    ) -> Self {
         Self {
             client,
             model,
             system_prompt,
             chat_id: uuid::Uuid::new_v4().to_string(),
+            generation,
+            tools: Vec::new(),
+            system_sent: false,
         }
     }
+
+    fn options(&self) -> OllamaGenerationOptions {
+        let mut options = OllamaGenerationOptions::default()
+            .num_ctx(self.generation.num_ctx.unwrap_or(DEFAULT_NUM_CTX));
+        if let Some(temperature) = self.generation.temperature {
+            options = options.temperature(temperature);
+        }
+        if let Some(top_p) = self.generation.top_p {
+            options = options.top_p(top_p);
+        }
+        if let Some(max_tokens) = self.generation.max_tokens {
+            options = options.num_predict(max_tokens);
+        }
+        if let Some(seed) = self.generation.seed {
+            options = options.seed(seed);
+        }
+        if !self.generation.stop.is_empty() {
+            options = options.stop(self.generation.stop.clone());
+        }
+        options
+    }
+
+    /// Builds the message(s) for a user turn, prepending the system prompt the first
+    /// time this chat is used and relying on Ollama's server-side history (via `chat_id`)
+    /// to remember it for every turn after that.
+    fn user_turn(&mut self, content: String) -> Vec<ChatMessage> {
+        let mut messages = Vec::new();
+        if !self.system_sent {
+            messages.push(ChatMessage {
+                role: MessageRole::System,
+                content: self.system_prompt.clone(),
+                images: None,
+            });
+            self.system_sent = true;
+        }
+        messages.push(ChatMessage {
+            role: MessageRole::User,
+            content,
+            images: None,
+        });
+        messages
+    }
+
+    async fn request_with_tools(&mut self, request: ChatMessageRequest) -> Result<ToolTurn> {
+        let request = request.tools(self.tools.clone());
+        let response = self
+            .client
+            .send_chat_messages_with_history(request, &self.chat_id)
+            .await?;
+        let message = response
+            .message
+            .ok_or_else(|| anyhow!("no message received from Ollama"))?;
+        if message.tool_calls.is_empty() {
+            return Ok(ToolTurn::Text(message.content));
+        }
+        let calls = message
+            .tool_calls
+            .into_iter()
+            .enumerate()
+            .map(|(i, call)| ToolCallRequest {
+                // Ollama doesn't assign tool call IDs like OpenAI does, so fall back to
+                // the call's position in this turn.
+                id: i.to_string(),
+                name: call.function.name,
+                arguments: call.function.arguments.to_string(),
+            })
+            .collect();
+        Ok(ToolTurn::ToolCalls(calls))
+    }
+}
+
+fn ollama_tools(tools: &[ToolDefinition]) -> Vec<ToolInfo> {
+    tools
+        .iter()
+        .map(|tool| ToolInfo::new(tool.name.to_string(), tool.description.to_string(), tool.parameters.clone()))
+        .collect()
 }
 
 impl ChatProvider for OllamaChatProvider {
     async fn send(&mut self, message: &str) -> Result<String> {
-        let msg = ChatMessage {
-            role: MessageRole::User,
-            content: self.render(message),
-            images: None,
-        };
-        let request = ChatMessageRequest::new(self.model.clone(), vec![msg]);
+        let messages = self.user_turn(message.to_string());
+        let request =
+            ChatMessageRequest::new(self.model.clone(), messages).options(self.options());
         let response = self
             .client
             .send_chat_messages_with_history(request, &self.chat_id)
@@ -41,7 +135,56 @@ impl ChatProvider for OllamaChatProvider {
             .map(|m| m.content)
     }
 
+    async fn send_stream(
+        &mut self,
+        message: &str,
+    ) -> Result<impl Stream<Item = Result<String>> + Send> {
+        let messages = self.user_turn(message.to_string());
+        let request =
+            ChatMessageRequest::new(self.model.clone(), messages).options(self.options());
+        let stream = self
+            .client
+            .send_chat_messages_with_history_stream(request, self.chat_id.clone())
+            .await
+            .map_err(|e| anyhow!("couldn't start streaming chat with Ollama: {e}"))?;
+        Ok(stream.map(|chunk| {
+            let chunk = chunk.map_err(|e| anyhow!("error reading stream chunk from Ollama: {e}"))?;
+            Ok(chunk.message.map(|m| m.content).unwrap_or_default())
+        }))
+    }
+
+    async fn send_with_tools(
+        &mut self,
+        message: &str,
+        tools: &[ToolDefinition],
+    ) -> Result<ToolTurn> {
+        self.tools = ollama_tools(tools);
+        let messages = self.user_turn(message.to_string());
+        let request =
+            ChatMessageRequest::new(self.model.clone(), messages).options(self.options());
+        self.request_with_tools(request).await
+    }
+
+    async fn continue_with_tool_results(
+        &mut self,
+        results: Vec<(String, String)>,
+    ) -> Result<ToolTurn> {
+        let messages = results
+            .into_iter()
+            .map(|(_tool_call_id, content)| ChatMessage {
+                role: MessageRole::Tool,
+                content,
+                images: None,
+            })
+            .collect::<Vec<_>>();
+        let request = ChatMessageRequest::new(self.model.clone(), messages).options(self.options());
+        self.request_with_tools(request).await
+    }
+
     fn render(&self, message: &str) -> String {
-        format!("{}\n{}", self.system_prompt, message)
+        // Unlike before conversation history was tracked, the system prompt is now sent
+        // once via `user_turn` as its own message rather than folded into the first user
+        // turn here, so this is just the user-facing text as-is.
+        message.to_string()
     }
 }
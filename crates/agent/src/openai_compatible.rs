@@ -0,0 +1,209 @@
+use anyhow::{Result, anyhow, bail};
+use futures::{Stream, StreamExt};
+use openai_api_rs::v1::api::OpenAIClient;
+use openai_api_rs::v1::chat_completion::{self, ChatCompletionRequest};
+
+use crate::generation::GenerationOptions;
+use crate::providers::{ChatProvider, ToolCallRequest, ToolTurn};
+use crate::tools::ToolDefinition;
+
+pub struct OpenAiCompatibleChatProvider {
+    client: OpenAIClient,
+    model: String,
+    /// Full conversation so far, starting with the system prompt, so the model retains
+    /// context of what it's already tried rather than re-reading the task from scratch.
+    messages: Vec<chat_completion::ChatCompletionMessage>,
+    generation: GenerationOptions,
+    /// The tools on offer for the current `--tools` run, resent on every request since
+    /// the API doesn't remember them between calls.
+    tools: Option<Vec<chat_completion::Tool>>,
+}
+
+impl OpenAiCompatibleChatProvider {
+    pub fn new(
+        client: OpenAIClient,
+        model: String,
+        system_prompt: String,
+        generation: GenerationOptions,
+    ) -> Self {
+        let messages = vec![chat_message(chat_completion::MessageRole::system, system_prompt)];
+        Self {
+            client,
+            model,
+            messages,
+            generation,
+            tools: None,
+        }
+    }
+
+    fn apply_generation_options(&self, req: &mut ChatCompletionRequest) {
+        req.temperature = self.generation.temperature.map(|t| t as f64);
+        req.top_p = self.generation.top_p.map(|t| t as f64);
+        req.max_tokens = self.generation.max_tokens;
+        req.seed = self.generation.seed.map(|s| s as i64);
+        if !self.generation.stop.is_empty() {
+            req.stop = Some(self.generation.stop.clone());
+        }
+    }
+
+    async fn request_with_tools(&mut self) -> Result<ToolTurn> {
+        let mut req = ChatCompletionRequest::new(self.model.clone(), self.messages.clone());
+        self.apply_generation_options(&mut req);
+        req.tools = self.tools.clone();
+        let response = self.client.chat_completion(req).await?;
+        let message = &response.choices[0].message;
+
+        match &message.tool_calls {
+            Some(tool_calls) if !tool_calls.is_empty() => {
+                let calls = tool_calls
+                    .iter()
+                    .map(|call| ToolCallRequest {
+                        id: call.id.clone(),
+                        name: call.function.name.clone().unwrap_or_default(),
+                        arguments: call.function.arguments.clone().unwrap_or_default(),
+                    })
+                    .collect();
+                self.messages.push(chat_completion::ChatCompletionMessage {
+                    role: chat_completion::MessageRole::assistant,
+                    content: chat_completion::Content::Text(String::new()),
+                    name: None,
+                    tool_calls: Some(tool_calls.clone()),
+                    tool_call_id: None,
+                });
+                Ok(ToolTurn::ToolCalls(calls))
+            }
+            _ => {
+                let content = message.content.clone().unwrap_or_default();
+                self.messages.push(chat_message(
+                    chat_completion::MessageRole::assistant,
+                    content.clone(),
+                ));
+                Ok(ToolTurn::Text(content))
+            }
+        }
+    }
+}
+
+fn openai_tools(tools: &[ToolDefinition]) -> Vec<chat_completion::Tool> {
+    tools
+        .iter()
+        .map(|tool| chat_completion::Tool {
+            r#type: chat_completion::ToolType::Function,
+            function: chat_completion::Function {
+                name: tool.name.to_string(),
+                description: Some(tool.description.to_string()),
+                parameters: tool.parameters.clone(),
+            },
+        })
+        .collect()
+}
+
+fn chat_message(
+    role: chat_completion::MessageRole,
+    content: String,
+) -> chat_completion::ChatCompletionMessage {
+    chat_completion::ChatCompletionMessage {
+        role,
+        content: chat_completion::Content::Text(content),
+        name: None,
+        tool_calls: None,
+        tool_call_id: None,
+    }
+}
+
+impl ChatProvider for OpenAiCompatibleChatProvider {
+    async fn send(&mut self, message: &str) -> Result<String> {
+        self.messages
+            .push(chat_message(chat_completion::MessageRole::user, message.to_string()));
+        let mut req = ChatCompletionRequest::new(self.model.clone(), self.messages.clone());
+        self.apply_generation_options(&mut req);
+        let response = self.client.chat_completion(req).await?;
+        let content = match &response.choices[0].message.content {
+            Some(content) => content,
+            None => bail!("no content in response"),
+        };
+        self.messages
+            .push(chat_message(chat_completion::MessageRole::assistant, content.clone()));
+        Ok(content.clone())
+    }
+
+    async fn send_stream(
+        &mut self,
+        message: &str,
+    ) -> Result<impl Stream<Item = Result<String>> + Send> {
+        self.messages
+            .push(chat_message(chat_completion::MessageRole::user, message.to_string()));
+        let mut req = ChatCompletionRequest::new(self.model.clone(), self.messages.clone());
+        self.apply_generation_options(&mut req);
+        req.stream = Some(true);
+        // The client sends `stream: true` and we're handed back the raw SSE `data:` events;
+        // each one decodes to a chunk carrying `choices[0].delta.content` until a `[DONE]` event.
+        let inner = self
+            .client
+            .chat_completion_stream(req)
+            .await
+            .map_err(|e| anyhow!("couldn't start streaming chat with provider: {e}"))?;
+
+        let state = (inner, String::new(), &mut self.messages);
+        Ok(futures::stream::unfold(
+            state,
+            |(mut inner, mut accumulated, messages)| async move {
+                match inner.next().await {
+                    Some(Ok(event)) => {
+                        let delta = event
+                            .choices
+                            .first()
+                            .and_then(|choice| choice.delta.content.clone())
+                            .unwrap_or_default();
+                        accumulated.push_str(&delta);
+                        Some((Ok(delta), (inner, accumulated, messages)))
+                    }
+                    Some(Err(e)) => Some((
+                        Err(anyhow!("error reading stream from provider: {e}")),
+                        (inner, accumulated, messages),
+                    )),
+                    None => {
+                        messages.push(chat_message(
+                            chat_completion::MessageRole::assistant,
+                            accumulated,
+                        ));
+                        None
+                    }
+                }
+            },
+        ))
+    }
+
+    async fn send_with_tools(
+        &mut self,
+        message: &str,
+        tools: &[ToolDefinition],
+    ) -> Result<ToolTurn> {
+        self.tools = Some(openai_tools(tools));
+        self.messages
+            .push(chat_message(chat_completion::MessageRole::user, message.to_string()));
+        self.request_with_tools().await
+    }
+
+    async fn continue_with_tool_results(
+        &mut self,
+        results: Vec<(String, String)>,
+    ) -> Result<ToolTurn> {
+        for (tool_call_id, content) in results {
+            self.messages.push(chat_completion::ChatCompletionMessage {
+                role: chat_completion::MessageRole::tool,
+                content: chat_completion::Content::Text(content),
+                name: None,
+                tool_calls: None,
+                tool_call_id: Some(tool_call_id),
+            });
+        }
+        self.request_with_tools().await
+    }
+
+    fn render(&self, message: &str) -> String {
+        // The system prompt already lives in `messages[0]` as its own `system` message,
+        // so folding it into the first user turn here would send it twice.
+        message.to_string()
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::Write;
+
+use anyhow::Result;
+use regex::Regex;
+
+/// What to do with a command the model wants to run, before it ever reaches `bash`.
+pub struct ExecutionPolicy {
+    approve: bool,
+    dry_run: bool,
+    deny: Vec<Regex>,
+}
+
+/// The outcome of resolving a proposed command against an [`ExecutionPolicy`].
+pub enum Decision {
+    /// Run this command (possibly edited from what the model proposed).
+    Execute(String),
+    /// Don't run anything; feed this synthetic output back to the model instead.
+    Synthetic { stdout: String, stderr: String },
+}
+
+/// Which kind of action an [`ExecutionPolicy`] is being asked to gate, so [`ExecutionPolicy::check`]
+/// can share its deny-list/dry-run/approval logic between `run_command` and `read_file` while
+/// still printing and prompting with wording that matches what's actually happening.
+enum Action {
+    RunCommand,
+    ReadFile,
+}
+
+impl Action {
+    fn noun(&self) -> &'static str {
+        match self {
+            Action::RunCommand => "command",
+            Action::ReadFile => "file read",
+        }
+    }
+
+    fn verb(&self) -> &'static str {
+        match self {
+            Action::RunCommand => "run",
+            Action::ReadFile => "read",
+        }
+    }
+
+    fn not_executed_message(&self) -> &'static str {
+        match self {
+            Action::RunCommand => "command not executed (--dry-run)",
+            Action::ReadFile => "file not read (--dry-run)",
+        }
+    }
+
+    fn prompt_question(&self) -> &'static str {
+        match self {
+            Action::RunCommand => "Run this command? [y/N/e(dit)] ",
+            Action::ReadFile => "Read this file? [y/N] ",
+        }
+    }
+}
+
+impl ExecutionPolicy {
+    pub fn new(approve: bool, dry_run: bool, deny: Vec<Regex>) -> Self {
+        Self {
+            approve,
+            dry_run,
+            deny,
+        }
+    }
+
+    /// Checks `command` against the deny-list and, if it survives, the approval and
+    /// dry-run settings. May block on stdin when `--approve` is set; that blocking read
+    /// is offloaded to a blocking-pool thread so it doesn't stall the signal handling in
+    /// `main.rs`'s `tokio::select!`.
+    pub async fn resolve(&self, command: String) -> Result<Decision> {
+        self.check(Action::RunCommand, command).await
+    }
+
+    /// Like [`ExecutionPolicy::resolve`], but for a `read_file` tool call: a file read
+    /// isn't a shell command, but it's still something the model is asking to do against
+    /// the real filesystem, so it goes through the same deny-list/dry-run/approval gates.
+    pub async fn resolve_read(&self, path: String) -> Result<Decision> {
+        self.check(Action::ReadFile, path).await
+    }
+
+    async fn check(&self, action: Action, subject: String) -> Result<Decision> {
+        if let Some(pattern) = self.deny.iter().find(|pattern| pattern.is_match(&subject)) {
+            println!("## {} rejected by deny-list", action.noun());
+            println!("{subject}");
+            println!();
+            return Ok(Decision::Synthetic {
+                stdout: String::new(),
+                stderr: format!(
+                    "{} rejected: matches deny-list pattern `{pattern}`",
+                    action.noun()
+                ),
+            });
+        }
+
+        if self.dry_run {
+            println!("## Would {} (--dry-run)", action.verb());
+            println!("{subject}");
+            println!();
+            return Ok(Decision::Synthetic {
+                stdout: String::new(),
+                stderr: action.not_executed_message().to_string(),
+            });
+        }
+
+        if self.approve {
+            return tokio::task::spawn_blocking(move || Self::prompt(action, subject)).await?;
+        }
+
+        Ok(Decision::Execute(subject))
+    }
+
+    fn prompt(action: Action, subject: String) -> Result<Decision> {
+        println!("## Proposed {}", action.noun());
+        println!("{subject}");
+        print!("{}", action.prompt_question());
+        std::io::stdout().flush()?;
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => Ok(Decision::Execute(subject)),
+            "e" | "edit" if matches!(action, Action::RunCommand) => {
+                print!("Edited command: ");
+                std::io::stdout().flush()?;
+                let mut edited = String::new();
+                std::io::stdin().read_line(&mut edited)?;
+                Ok(Decision::Execute(edited.trim().to_string()))
+            }
+            _ => Ok(Decision::Synthetic {
+                stdout: String::new(),
+                stderr: format!("{} rejected by user", action.noun()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_deny_list() -> Result<()> {
+        let policy = ExecutionPolicy::new(false, false, vec![Regex::new("rm -rf")?]);
+
+        let decision = policy.resolve("rm -rf /".to_string()).await?;
+        match decision {
+            Decision::Synthetic { stdout, stderr } => {
+                assert_eq!(stdout, "");
+                assert!(stderr.contains("rejected"));
+            }
+            Decision::Execute(_) => panic!("expected a denied command not to execute"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_dry_run_short_circuits_before_approval() -> Result<()> {
+        let policy = ExecutionPolicy::new(true, true, vec![]);
+
+        let decision = policy.resolve("ls -la".to_string()).await?;
+        match decision {
+            Decision::Synthetic { stdout, stderr } => {
+                assert_eq!(stdout, "");
+                assert!(stderr.contains("--dry-run"));
+            }
+            Decision::Execute(_) => panic!("--dry-run should never execute, even with --approve"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_executes_by_default() -> Result<()> {
+        let policy = ExecutionPolicy::new(false, false, vec![]);
+
+        let decision = policy.resolve("ls -la".to_string()).await?;
+        match decision {
+            Decision::Execute(command) => assert_eq!(command, "ls -la"),
+            Decision::Synthetic { .. } => panic!("expected the command to execute"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_deny_list_checked_before_dry_run() -> Result<()> {
+        let policy = ExecutionPolicy::new(false, true, vec![Regex::new("rm -rf")?]);
+
+        let decision = policy.resolve("rm -rf /".to_string()).await?;
+        match decision {
+            Decision::Synthetic { stderr, .. } => {
+                assert!(stderr.contains("deny-list"));
+            }
+            Decision::Execute(_) => panic!("expected a denied command not to execute"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_read_dry_run_blocks_reads_too() -> Result<()> {
+        let policy = ExecutionPolicy::new(false, true, vec![]);
+
+        let decision = policy.resolve_read("secrets.txt".to_string()).await?;
+        match decision {
+            Decision::Synthetic { stdout, stderr } => {
+                assert_eq!(stdout, "");
+                assert!(stderr.contains("--dry-run"));
+            }
+            Decision::Execute(_) => panic!("--dry-run should never read"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_read_deny_list() -> Result<()> {
+        let policy = ExecutionPolicy::new(false, false, vec![Regex::new("secrets")?]);
+
+        let decision = policy.resolve_read("secrets.txt".to_string()).await?;
+        match decision {
+            Decision::Synthetic { stdout, stderr } => {
+                assert_eq!(stdout, "");
+                assert!(stderr.contains("rejected"));
+            }
+            Decision::Execute(_) => panic!("expected a denied read not to execute"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_read_executes_by_default() -> Result<()> {
+        let policy = ExecutionPolicy::new(false, false, vec![]);
+
+        let decision = policy.resolve_read("notes.txt".to_string()).await?;
+        match decision {
+            Decision::Execute(path) => assert_eq!(path, "notes.txt"),
+            Decision::Synthetic { .. } => panic!("expected the read to execute"),
+        }
+        Ok(())
+    }
+}
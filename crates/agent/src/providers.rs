@@ -1,20 +1,84 @@
 use anyhow::Result;
+use futures::Stream;
 
 use crate::ollama::OllamaChatProvider;
-use crate::openrouter::OpenRouterChatProvider;
+use crate::openai_compatible::OpenAiCompatibleChatProvider;
+use crate::tools::ToolDefinition;
+
+/// A single `tool_calls` entry the model asked us to run.
+pub struct ToolCallRequest {
+    pub id: String,
+    pub name: String,
+    pub arguments: String,
+}
+
+/// What the model did with its turn in `--tools` mode.
+pub enum ToolTurn {
+    /// The model wants to invoke one or more tools before continuing.
+    ToolCalls(Vec<ToolCallRequest>),
+    /// The model replied with plain text instead of calling a tool.
+    Text(String),
+}
 
 #[allow(clippy::large_enum_variant)]
 pub enum ChatProviders {
     Ollama(OllamaChatProvider),
-    OpenRouter(OpenRouterChatProvider),
+    OpenAiCompatible(OpenAiCompatibleChatProvider),
 }
 
 impl ChatProvider for ChatProviders {
     async fn send(&mut self, message: &str) -> Result<String> {
         match self {
             ChatProviders::Ollama(ollama_chat_provider) => ollama_chat_provider.send(message).await,
-            ChatProviders::OpenRouter(open_router_chat_provider) => {
-                open_router_chat_provider.send(message).await
+            ChatProviders::OpenAiCompatible(openai_compatible_chat_provider) => {
+                openai_compatible_chat_provider.send(message).await
+            }
+        }
+    }
+
+    async fn send_stream(
+        &mut self,
+        message: &str,
+    ) -> Result<std::pin::Pin<Box<dyn Stream<Item = Result<String>> + Send + '_>>> {
+        match self {
+            ChatProviders::Ollama(ollama_chat_provider) => {
+                Ok(Box::pin(ollama_chat_provider.send_stream(message).await?))
+            }
+            ChatProviders::OpenAiCompatible(openai_compatible_chat_provider) => Ok(Box::pin(
+                openai_compatible_chat_provider.send_stream(message).await?,
+            )),
+        }
+    }
+
+    async fn send_with_tools(
+        &mut self,
+        message: &str,
+        tools: &[ToolDefinition],
+    ) -> Result<ToolTurn> {
+        match self {
+            ChatProviders::Ollama(ollama_chat_provider) => {
+                ollama_chat_provider.send_with_tools(message, tools).await
+            }
+            ChatProviders::OpenAiCompatible(openai_compatible_chat_provider) => {
+                openai_compatible_chat_provider
+                    .send_with_tools(message, tools)
+                    .await
+            }
+        }
+    }
+
+    async fn continue_with_tool_results(
+        &mut self,
+        results: Vec<(String, String)>,
+    ) -> Result<ToolTurn> {
+        match self {
+            ChatProviders::Ollama(ollama_chat_provider) => {
+                ollama_chat_provider.continue_with_tool_results(results).await
+            }
+            ChatProviders::OpenAiCompatible(openai_compatible_chat_provider) => {
+                openai_compatible_chat_provider
+                    .continue_with_tool_results(results)
+                    .await
             }
         }
     }
@@ -22,15 +86,38 @@ impl ChatProvider for ChatProviders {
     fn render(&self, message: &str) -> String {
         match self {
             ChatProviders::Ollama(ollama_chat_provider) => ollama_chat_provider.render(message),
-            ChatProviders::OpenRouter(open_router_chat_provider) => {
-                open_router_chat_provider.render(message)
+            ChatProviders::OpenAiCompatible(openai_compatible_chat_provider) => {
+                openai_compatible_chat_provider.render(message)
             }
         }
     }
 }
 
 pub trait ChatProvider {
+    /// Sends `message` and waits for the full response, used as the `--no-stream` fallback.
     #[allow(async_fn_in_trait)]
     async fn send(&mut self, message: &str) -> Result<String>;
+    /// Like [`ChatProvider::send`], but yields the response incrementally as the model
+    /// generates it rather than waiting for the full completion.
+    #[allow(async_fn_in_trait)]
+    async fn send_stream(
+        &mut self,
+        message: &str,
+    ) -> Result<impl Stream<Item = Result<String>> + Send>;
+    /// Sends `message` alongside `tools`, using the provider's native tool-calling support
+    /// instead of the YAML-fenced `thoughts`/`run` protocol.
+    #[allow(async_fn_in_trait)]
+    async fn send_with_tools(
+        &mut self,
+        message: &str,
+        tools: &[ToolDefinition],
+    ) -> Result<ToolTurn>;
+    /// Feeds back `(tool_call_id, content)` results for the tool calls from the previous
+    /// turn and gets the model's next turn.
+    #[allow(async_fn_in_trait)]
+    async fn continue_with_tool_results(
+        &mut self,
+        results: Vec<(String, String)>,
+    ) -> Result<ToolTurn>;
     fn render(&self, message: &str) -> String;
 }
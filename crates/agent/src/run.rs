@@ -1,46 +1,80 @@
+use std::io::Write;
+
 use anyhow::Result;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 
-use crate::providers::ChatProvider;
+use crate::policy::{Decision, ExecutionPolicy};
+use crate::providers::{ChatProvider, ToolTurn};
+use crate::tools::{self, ToolCall};
 
-pub async fn run_agent(mut chat_provider: impl ChatProvider, mut message: String) -> Result<()> {
+pub async fn run_agent(
+    mut chat_provider: impl ChatProvider,
+    mut message: String,
+    policy: ExecutionPolicy,
+    stream: bool,
+) -> Result<()> {
     println!("> Sending first request (may take a short while if using Ollama)");
     let mut i = 0;
     loop {
         i += 1;
-        let response = chat_provider.send(&message).await?;
         println!("## Response {}", i);
-        println!("{}", response);
+        let response = if stream {
+            let mut chunks = Box::pin(chat_provider.send_stream(&message).await?);
+            let mut response = String::new();
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk?;
+                print!("{}", chunk);
+                std::io::stdout().flush()?;
+                response.push_str(&chunk);
+            }
+            drop(chunks);
+            response
+        } else {
+            let response = chat_provider.send(&message).await?;
+            print!("{}", response);
+            response
+        };
+        println!();
         println!();
         let output = match parse(&response) {
             Ok(response) => {
                 if response.run.trim_ascii_start().trim_ascii_end().eq("STOP") {
                     None
                 } else {
-                    let mut cmd = tokio::process::Command::new("bash");
-                    cmd.arg("-c");
-                    cmd.args(vec![response.run]);
-                    match cmd.output().await {
-                        Ok(output) => {
-                            let stdout = String::from_utf8(output.stdout.clone())?;
-                            tracing::debug!(stdout, "stdout");
-                            let stderr = String::from_utf8(output.stderr.clone())?;
-                            tracing::debug!(stderr, "stderr");
-                            Some(CommandOutput {
-                                stdout,
-                                stderr,
-                                exit_code: output.status.code(),
-                            })
-                        }
-                        Err(err) => {
-                            println!("## Error trying to run command");
-                            println!();
-                            println!("{}", err);
-                            Some(CommandOutput {
-                                stdout: "Error trying to run command".to_string(),
-                                stderr: err.to_string(),
-                                exit_code: None,
-                            })
+                    match policy.resolve(response.run).await? {
+                        Decision::Synthetic { stdout, stderr } => Some(CommandOutput {
+                            stdout,
+                            stderr,
+                            exit_code: None,
+                        }),
+                        Decision::Execute(command) => {
+                            let mut cmd = tokio::process::Command::new("bash");
+                            cmd.arg("-c");
+                            cmd.args(vec![command]);
+                            match cmd.output().await {
+                                Ok(output) => {
+                                    let stdout = String::from_utf8(output.stdout.clone())?;
+                                    tracing::debug!(stdout, "stdout");
+                                    let stderr = String::from_utf8(output.stderr.clone())?;
+                                    tracing::debug!(stderr, "stderr");
+                                    Some(CommandOutput {
+                                        stdout,
+                                        stderr,
+                                        exit_code: output.status.code(),
+                                    })
+                                }
+                                Err(err) => {
+                                    println!("## Error trying to run command");
+                                    println!();
+                                    println!("{}", err);
+                                    Some(CommandOutput {
+                                        stdout: "Error trying to run command".to_string(),
+                                        stderr: err.to_string(),
+                                        exit_code: None,
+                                    })
+                                }
+                            }
                         }
                     }
                 }
@@ -70,6 +104,97 @@ pub async fn run_agent(mut chat_provider: impl ChatProvider, mut message: String
     Ok(())
 }
 
+/// Like [`run_agent`], but drives the model through its native tool-calling support
+/// (`run_command`, `read_file`, `finish`) instead of scraping a YAML-fenced code block.
+pub async fn run_agent_tools(
+    mut chat_provider: impl ChatProvider,
+    message: String,
+    policy: ExecutionPolicy,
+) -> Result<()> {
+    println!("> Sending first request (may take a short while if using Ollama)");
+    let tools = tools::catalog();
+    let mut turn = chat_provider.send_with_tools(&message, &tools).await?;
+    let mut i = 0;
+    loop {
+        i += 1;
+        println!("## Response {}", i);
+        let calls = match turn {
+            ToolTurn::Text(text) => {
+                println!("{text}");
+                break;
+            }
+            ToolTurn::ToolCalls(calls) => calls,
+        };
+
+        let mut results = Vec::with_capacity(calls.len());
+        let mut finished = false;
+        for call in calls {
+            println!("- {}({})", call.name, call.arguments);
+            let content = match tools::parse_call(&call.name, &call.arguments) {
+                Ok(ToolCall::RunCommand(args)) => run_command(&policy, args.command).await?,
+                Ok(ToolCall::ReadFile(args)) => read_file(&policy, args.path).await?,
+                Ok(ToolCall::Finish(args)) => {
+                    finished = true;
+                    args.summary
+                }
+                Err(err) => err.to_string(),
+            };
+            println!("{content}");
+            results.push((call.id, content));
+            if finished {
+                // The model won't see results for any further calls in this batch, so
+                // don't run them.
+                break;
+            }
+        }
+        println!();
+
+        if finished {
+            break;
+        }
+        turn = chat_provider.continue_with_tool_results(results).await?;
+    }
+    Ok(())
+}
+
+async fn run_command(policy: &ExecutionPolicy, command: String) -> Result<String> {
+    let output = match policy.resolve(command).await? {
+        Decision::Synthetic { stdout, stderr } => CommandOutput {
+            stdout,
+            stderr,
+            exit_code: None,
+        },
+        Decision::Execute(command) => {
+            let mut cmd = tokio::process::Command::new("bash");
+            cmd.arg("-c");
+            cmd.args(vec![command]);
+            match cmd.output().await {
+                Ok(output) => CommandOutput {
+                    stdout: String::from_utf8(output.stdout)?,
+                    stderr: String::from_utf8(output.stderr)?,
+                    exit_code: output.status.code(),
+                },
+                Err(err) => CommandOutput {
+                    stdout: "Error trying to run command".to_string(),
+                    stderr: err.to_string(),
+                    exit_code: None,
+                },
+            }
+        }
+    };
+    Ok(serde_yml::to_string(&output)?)
+}
+
+async fn read_file(policy: &ExecutionPolicy, path: String) -> Result<String> {
+    match policy.resolve_read(path).await? {
+        Decision::Synthetic { stdout, stderr } => Ok(if stderr.is_empty() { stdout } else { stderr }),
+        Decision::Execute(path) => Ok(match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(err) => format!("error reading file {}: {err}", path),
+        }),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct Response {
     #[allow(dead_code)] // only used during deserialization
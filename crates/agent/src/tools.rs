@@ -0,0 +1,134 @@
+use anyhow::{Result, bail};
+use serde::Deserialize;
+use serde_json::json;
+
+/// A tool the model can call, described once and handed to whichever provider is in use.
+#[derive(Debug, Clone)]
+pub struct ToolDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub parameters: serde_json::Value,
+}
+
+/// The fixed set of tools available in `--tools` mode.
+pub fn catalog() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: "run_command",
+            description: "Run a bash command and get back its stdout, stderr and exit code.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "The bash command to run",
+                    },
+                },
+                "required": ["command"],
+            }),
+        },
+        ToolDefinition {
+            name: "read_file",
+            description: "Read the contents of a file at the given path.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the file to read, relative to the working directory",
+                    },
+                },
+                "required": ["path"],
+            }),
+        },
+        ToolDefinition {
+            name: "finish",
+            description: "Call this once the task is complete, with a short summary of what was done.",
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "summary": {
+                        "type": "string",
+                        "description": "A short summary of what was done",
+                    },
+                },
+                "required": ["summary"],
+            }),
+        },
+    ]
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunCommandArgs {
+    pub command: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReadFileArgs {
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinishArgs {
+    pub summary: String,
+}
+
+pub enum ToolCall {
+    RunCommand(RunCommandArgs),
+    ReadFile(ReadFileArgs),
+    Finish(FinishArgs),
+}
+
+/// Parses the `(name, arguments)` pair the model returns in a `tool_calls` entry into
+/// one of our known tools.
+pub fn parse_call(name: &str, arguments: &str) -> Result<ToolCall> {
+    match name {
+        "run_command" => Ok(ToolCall::RunCommand(serde_json::from_str(arguments)?)),
+        "read_file" => Ok(ToolCall::ReadFile(serde_json::from_str(arguments)?)),
+        "finish" => Ok(ToolCall::Finish(serde_json::from_str(arguments)?)),
+        other => bail!("model called unknown tool `{other}`"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_call_run_command() -> Result<()> {
+        match parse_call("run_command", r#"{"command": "ls -la"}"#)? {
+            ToolCall::RunCommand(args) => assert_eq!(args.command, "ls -la"),
+            _ => panic!("expected a RunCommand call"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_call_read_file() -> Result<()> {
+        match parse_call("read_file", r#"{"path": "task.md"}"#)? {
+            ToolCall::ReadFile(args) => assert_eq!(args.path, "task.md"),
+            _ => panic!("expected a ReadFile call"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_call_finish() -> Result<()> {
+        match parse_call("finish", r#"{"summary": "done"}"#)? {
+            ToolCall::Finish(args) => assert_eq!(args.summary, "done"),
+            _ => panic!("expected a Finish call"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_call_missing_required_field() {
+        assert!(parse_call("run_command", "{}").is_err());
+    }
+
+    #[test]
+    fn test_parse_call_unknown_tool() {
+        let err = parse_call("delete_everything", "{}").unwrap_err();
+        assert!(err.to_string().contains("delete_everything"));
+    }
+}